@@ -0,0 +1,183 @@
+//! Serialization of photometry catalogs into downloadable products.
+//!
+//! The same catalog can be emitted as CSV, JSON, or a FITS binary-table
+//! extension. These are written to disk when a reduction finishes and streamed
+//! back by the results route.
+
+use crate::photometry::SourcePhotometry;
+
+/// A product format the results route can negotiate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    Json,
+    Fits,
+}
+
+impl Format {
+    /// File extension used on disk.
+    pub fn ext(self) -> &'static str {
+        match self {
+            Format::Csv => "csv",
+            Format::Json => "json",
+            Format::Fits => "fits",
+        }
+    }
+
+    /// MIME type sent in `Content-Type`.
+    pub fn mime(self) -> &'static str {
+        match self {
+            Format::Csv => "text/csv",
+            Format::Json => "application/json",
+            Format::Fits => "application/fits",
+        }
+    }
+
+    /// Negotiate a format from an `Accept` header value, defaulting to JSON.
+    pub fn negotiate(accept: Option<&str>) -> Format {
+        let accept = accept.unwrap_or("");
+        if accept.contains("text/csv") {
+            Format::Csv
+        } else if accept.contains("application/fits") || accept.contains("image/fits") {
+            Format::Fits
+        } else {
+            Format::Json
+        }
+    }
+}
+
+/// Render a catalog as CSV with a header row.
+pub fn to_csv(catalog: &[SourcePhotometry]) -> String {
+    let mut out = String::from("x,y,flux,flux_err,mag,mag_err,clipped\n");
+    for s in catalog {
+        out.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            s.x, s.y, s.flux, s.flux_err, s.mag, s.mag_err, s.clipped
+        ));
+    }
+    out
+}
+
+/// Render a catalog as compact JSON.
+pub fn to_json(catalog: &[SourcePhotometry]) -> Result<Vec<u8>, serde_json::Error> {
+    serde_json::to_vec(catalog)
+}
+
+/// Render a catalog as a FITS binary-table extension.
+///
+/// The primary HDU is a dataless stub followed by a single `BINTABLE` with one
+/// 64-bit float column per measured quantity, padded to the 2880-byte blocking
+/// required by the standard.
+pub fn to_fits(catalog: &[SourcePhotometry]) -> Vec<u8> {
+    const BLOCK: usize = 2880;
+    let columns: [(&str, fn(&SourcePhotometry) -> f64); 6] = [
+        ("X", |s| s.x),
+        ("Y", |s| s.y),
+        ("FLUX", |s| s.flux),
+        ("FLUX_ERR", |s| s.flux_err),
+        ("MAG", |s| s.mag),
+        ("MAG_ERR", |s| s.mag_err),
+    ];
+
+    let mut out = Vec::new();
+    push_header(&mut out, &primary_cards());
+
+    let naxis1 = columns.len() * 8;
+    let naxis2 = catalog.len();
+    let mut table_cards = vec![
+        card("XTENSION", "'BINTABLE'"),
+        card("BITPIX", "8"),
+        card("NAXIS", "2"),
+        card("NAXIS1", &naxis1.to_string()),
+        card("NAXIS2", &naxis2.to_string()),
+        card("PCOUNT", "0"),
+        card("GCOUNT", "1"),
+        card("TFIELDS", &columns.len().to_string()),
+    ];
+    for (i, (name, _)) in columns.iter().enumerate() {
+        table_cards.push(card(&format!("TTYPE{}", i + 1), &format!("'{name}'")));
+        table_cards.push(card(&format!("TFORM{}", i + 1), "'1D'"));
+    }
+    push_header(&mut out, &table_cards);
+
+    let data_start = out.len();
+    for row in catalog {
+        for (_, get) in &columns {
+            out.extend_from_slice(&get(row).to_be_bytes());
+        }
+    }
+    pad_to_block(&mut out, data_start, BLOCK, 0);
+
+    out
+}
+
+fn primary_cards() -> Vec<String> {
+    vec![
+        card("SIMPLE", "T"),
+        card("BITPIX", "8"),
+        card("NAXIS", "0"),
+        card("EXTEND", "T"),
+    ]
+}
+
+fn card(key: &str, value: &str) -> String {
+    format!("{key:<8}= {value:>20}")
+}
+
+fn push_header(out: &mut Vec<u8>, cards: &[String]) {
+    const BLOCK: usize = 2880;
+    const CARD: usize = 80;
+    let start = out.len();
+    for c in cards {
+        let mut bytes = c.clone().into_bytes();
+        bytes.resize(CARD, b' ');
+        out.extend_from_slice(&bytes);
+    }
+    let mut end = b"END".to_vec();
+    end.resize(CARD, b' ');
+    out.extend_from_slice(&end);
+    pad_to_block(out, start, BLOCK, b' ');
+}
+
+fn pad_to_block(out: &mut Vec<u8>, start: usize, block: usize, fill: u8) {
+    while (out.len() - start) % block != 0 {
+        out.push(fill);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Vec<SourcePhotometry> {
+        vec![SourcePhotometry {
+            x: 10.0,
+            y: 20.0,
+            flux: 1234.5,
+            flux_err: 12.3,
+            mag: 15.5,
+            mag_err: 0.01,
+            clipped: false,
+        }]
+    }
+
+    #[test]
+    fn test_csv_has_header_and_row() {
+        let csv = to_csv(&sample());
+        assert!(csv.starts_with("x,y,flux,flux_err,mag,mag_err,clipped\n"));
+        assert_eq!(csv.lines().count(), 2);
+    }
+
+    #[test]
+    fn test_fits_is_block_aligned() {
+        let fits = to_fits(&sample());
+        assert_eq!(fits.len() % 2880, 0);
+    }
+
+    #[test]
+    fn test_negotiate_defaults_to_json() {
+        assert_eq!(Format::negotiate(None), Format::Json);
+        assert_eq!(Format::negotiate(Some("text/csv")), Format::Csv);
+        assert_eq!(Format::negotiate(Some("application/fits")), Format::Fits);
+    }
+}
@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Runtime configuration for the photometry service.
+///
+/// Values are cheap to clone so the config can be threaded into handler
+/// state and shared across the worker pool.
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Directory under which uploaded frames and reduced products are stored.
+    pub storage_dir: PathBuf,
+    /// Detector gain in electrons per ADU, used for Poisson error propagation.
+    pub gain: f64,
+    /// Photometric zeropoint applied when converting flux to magnitude.
+    pub zeropoint: f64,
+    /// Aperture and sky-annulus radii, in pixels.
+    pub apertures: ApertureConfig,
+    /// Remote-fetch limits applied to `POST /images/fetch`.
+    pub fetch: FetchConfig,
+    /// Seconds to wait for in-flight jobs to drain on shutdown.
+    pub shutdown_grace_secs: u64,
+    /// Valid API keys mapped to the principal they authenticate as. Mutating
+    /// routes reject any request whose key is not in this map.
+    pub api_keys: HashMap<String, String>,
+}
+
+/// Guards on downloading remote FITS frames so a large survey tile cannot be
+/// buffered into memory unbounded.
+#[derive(Debug, Clone)]
+pub struct FetchConfig {
+    /// Maximum number of bytes accepted from a remote URL.
+    pub max_size: u64,
+    /// Content types accepted from the remote server.
+    pub allowed_content_types: Vec<String>,
+}
+
+impl Default for FetchConfig {
+    fn default() -> Self {
+        Self {
+            max_size: 512 * 1024 * 1024,
+            allowed_content_types: vec![
+                "application/fits".to_string(),
+                "image/fits".to_string(),
+                "application/octet-stream".to_string(),
+            ],
+        }
+    }
+}
+
+/// Circular aperture and sky annulus geometry.
+#[derive(Debug, Clone, Copy)]
+pub struct ApertureConfig {
+    /// Radius of the source aperture.
+    pub radius: f64,
+    /// Inner radius of the sky annulus.
+    pub sky_inner: f64,
+    /// Outer radius of the sky annulus.
+    pub sky_outer: f64,
+}
+
+impl Default for ApertureConfig {
+    fn default() -> Self {
+        Self {
+            radius: 5.0,
+            sky_inner: 8.0,
+            sky_outer: 12.0,
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            storage_dir: PathBuf::from("storage"),
+            gain: 1.0,
+            zeropoint: 25.0,
+            apertures: ApertureConfig::default(),
+            fetch: FetchConfig::default(),
+            shutdown_grace_secs: 30,
+            api_keys: HashMap::new(),
+        }
+    }
+}
@@ -0,0 +1,201 @@
+//! Minimal FITS image reader.
+//!
+//! Only the primary HDU of a simple 2-D image is decoded — enough to pull the
+//! pixel array out of an uploaded frame for photometry. The header is parsed
+//! from the fixed 2880-byte blocks defined by the FITS standard; BITPIX values
+//! of 8/16/32/-32/-64 are supported and scaled by the optional `BZERO`/`BSCALE`
+//! keywords.
+
+use std::fmt;
+
+const BLOCK: usize = 2880;
+const CARD: usize = 80;
+
+/// A decoded 2-D image in row-major order, with `data[y * width + x]` holding
+/// the physical pixel value.
+#[derive(Debug, Clone)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub data: Vec<f64>,
+}
+
+impl Image {
+    /// Physical value of pixel `(x, y)`; callers guarantee bounds.
+    #[inline]
+    pub fn at(&self, x: usize, y: usize) -> f64 {
+        self.data[y * self.width + x]
+    }
+}
+
+/// Errors raised while decoding a FITS frame.
+#[derive(Debug)]
+pub enum FitsError {
+    Truncated,
+    MissingKeyword(&'static str),
+    UnsupportedBitpix(i64),
+    UnsupportedDimensions(i64),
+}
+
+impl fmt::Display for FitsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FitsError::Truncated => write!(f, "FITS data truncated"),
+            FitsError::MissingKeyword(k) => write!(f, "missing required keyword {k}"),
+            FitsError::UnsupportedBitpix(b) => write!(f, "unsupported BITPIX {b}"),
+            FitsError::UnsupportedDimensions(n) => write!(f, "unsupported NAXIS {n}"),
+        }
+    }
+}
+
+impl std::error::Error for FitsError {}
+
+/// Decode the primary image HDU of `bytes`.
+pub fn read_image(bytes: &[u8]) -> Result<Image, FitsError> {
+    let mut cards = Vec::new();
+    let mut offset = 0;
+    let mut header_end = None;
+
+    'outer: while offset + BLOCK <= bytes.len() {
+        for c in 0..(BLOCK / CARD) {
+            let start = offset + c * CARD;
+            let card = &bytes[start..start + CARD];
+            if card.starts_with(b"END ") || card == b"END".as_slice() || &card[..3] == b"END" {
+                header_end = Some(offset + BLOCK);
+                break 'outer;
+            }
+            cards.push(card.to_vec());
+        }
+        offset += BLOCK;
+    }
+
+    let data_start = header_end.ok_or(FitsError::Truncated)?;
+
+    let bitpix = keyword_int(&cards, "BITPIX").ok_or(FitsError::MissingKeyword("BITPIX"))?;
+    let naxis = keyword_int(&cards, "NAXIS").ok_or(FitsError::MissingKeyword("NAXIS"))?;
+    if naxis != 2 {
+        return Err(FitsError::UnsupportedDimensions(naxis));
+    }
+    let width = keyword_int(&cards, "NAXIS1").ok_or(FitsError::MissingKeyword("NAXIS1"))? as usize;
+    let height = keyword_int(&cards, "NAXIS2").ok_or(FitsError::MissingKeyword("NAXIS2"))? as usize;
+    let bzero = keyword_float(&cards, "BZERO").unwrap_or(0.0);
+    let bscale = keyword_float(&cards, "BSCALE").unwrap_or(1.0);
+
+    let count = width * height;
+    let mut data = Vec::with_capacity(count);
+    let mut cursor = data_start;
+
+    for _ in 0..count {
+        let raw = read_pixel(bytes, &mut cursor, bitpix)?;
+        data.push(bzero + bscale * raw);
+    }
+
+    Ok(Image {
+        width,
+        height,
+        data,
+    })
+}
+
+fn read_pixel(bytes: &[u8], cursor: &mut usize, bitpix: i64) -> Result<f64, FitsError> {
+    macro_rules! take {
+        ($n:expr) => {{
+            let end = *cursor + $n;
+            if end > bytes.len() {
+                return Err(FitsError::Truncated);
+            }
+            let slice = &bytes[*cursor..end];
+            *cursor = end;
+            slice
+        }};
+    }
+
+    let value = match bitpix {
+        8 => take!(1)[0] as f64,
+        16 => i16::from_be_bytes(take!(2).try_into().unwrap()) as f64,
+        32 => i32::from_be_bytes(take!(4).try_into().unwrap()) as f64,
+        -32 => f32::from_be_bytes(take!(4).try_into().unwrap()) as f64,
+        -64 => f64::from_be_bytes(take!(8).try_into().unwrap()),
+        other => return Err(FitsError::UnsupportedBitpix(other)),
+    };
+    Ok(value)
+}
+
+fn keyword_value(cards: &[Vec<u8>], key: &str) -> Option<String> {
+    for card in cards {
+        let text = String::from_utf8_lossy(card);
+        if text.len() >= 8 && text[..8].trim_end() == key {
+            let rest = text.get(8..)?;
+            let rest = rest.trim_start();
+            let rest = rest.strip_prefix('=')?.trim();
+            // Drop any inline comment after the value.
+            let value = rest.split('/').next().unwrap_or("").trim();
+            return Some(value.to_string());
+        }
+    }
+    None
+}
+
+fn keyword_int(cards: &[Vec<u8>], key: &str) -> Option<i64> {
+    keyword_value(cards, key)?.parse().ok()
+}
+
+fn keyword_float(cards: &[Vec<u8>], key: &str) -> Option<f64> {
+    keyword_value(cards, key)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a minimal valid FITS frame with `-64` (f64) pixels.
+    fn synth_fits(width: usize, height: usize, fill: f64) -> Vec<u8> {
+        let mut header = String::new();
+        let push = |h: &mut String, card: String| {
+            let mut c = card;
+            c.truncate(CARD);
+            while c.len() < CARD {
+                c.push(' ');
+            }
+            h.push_str(&c);
+        };
+        push(&mut header, format!("{:<8}= {:>20}", "SIMPLE", "T"));
+        push(&mut header, format!("{:<8}= {:>20}", "BITPIX", "-64"));
+        push(&mut header, format!("{:<8}= {:>20}", "NAXIS", "2"));
+        push(&mut header, format!("{:<8}= {:>20}", "NAXIS1", width));
+        push(&mut header, format!("{:<8}= {:>20}", "NAXIS2", height));
+        push(&mut header, "END".to_string());
+
+        let mut bytes = header.into_bytes();
+        while bytes.len() % BLOCK != 0 {
+            bytes.push(b' ');
+        }
+        for _ in 0..(width * height) {
+            bytes.extend_from_slice(&fill.to_be_bytes());
+        }
+        while bytes.len() % BLOCK != 0 {
+            bytes.push(0);
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_reads_flat_frame() {
+        let bytes = synth_fits(4, 3, 42.0);
+        let image = read_image(&bytes).unwrap();
+        assert_eq!(image.width, 4);
+        assert_eq!(image.height, 3);
+        assert_eq!(image.data.len(), 12);
+        assert!(image.data.iter().all(|v| (*v - 42.0).abs() < 1e-9));
+    }
+
+    #[test]
+    fn test_missing_bitpix_errors() {
+        let mut bytes = vec![b' '; BLOCK];
+        bytes[..3].copy_from_slice(b"END");
+        assert!(matches!(
+            read_image(&bytes),
+            Err(FitsError::MissingKeyword("BITPIX"))
+        ));
+    }
+}
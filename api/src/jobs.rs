@@ -0,0 +1,366 @@
+//! Asynchronous photometry job subsystem.
+//!
+//! A [`JobManager`] holds the job table in shared state and feeds a pool of
+//! background workers over an mpsc channel. Submitting a job returns its id
+//! immediately; clients poll [`JobManager::status`] until the reduction is done
+//! and a result is available for download.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::sync::{mpsc, RwLock};
+use uuid::Uuid;
+
+use crate::catalog;
+use crate::config::Config;
+use crate::fits;
+use crate::photometry;
+use crate::storage::{ImageId, Storage};
+
+/// Opaque identifier for a reduction job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct JobId(Uuid);
+
+impl JobId {
+    fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Uuid::parse_str(s).ok().map(Self)
+    }
+}
+
+impl fmt::Display for JobId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Lifecycle state of a job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Done,
+    Failed,
+}
+
+/// Point-in-time view of a job, returned by `GET /jobs/{id}`.
+#[derive(Debug, Clone, Serialize)]
+pub struct JobState {
+    pub status: JobStatus,
+    pub progress: f32,
+    pub result_url: Option<String>,
+}
+
+/// Shared, cloneable handle to the job subsystem.
+#[derive(Clone)]
+pub struct JobManager {
+    jobs: Arc<RwLock<HashMap<JobId, JobState>>>,
+    owners: Arc<RwLock<HashMap<JobId, String>>>,
+    tx: mpsc::UnboundedSender<Task>,
+    draining: Arc<AtomicBool>,
+}
+
+/// Reasons a submission can be rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubmitError {
+    /// The server is shutting down and no longer accepts new jobs.
+    Draining,
+}
+
+struct Task {
+    id: JobId,
+    image: ImageId,
+}
+
+impl JobManager {
+    /// Build a manager and spawn `workers` background processors.
+    pub fn new(storage: Storage, config: Config, workers: usize) -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let jobs: Arc<RwLock<HashMap<JobId, JobState>>> = Arc::new(RwLock::new(HashMap::new()));
+        let draining = Arc::new(AtomicBool::new(false));
+        spawn_pool(rx, jobs.clone(), storage, config, draining.clone(), workers);
+        Self {
+            jobs,
+            owners: Arc::new(RwLock::new(HashMap::new())),
+            tx,
+            draining,
+        }
+    }
+
+    /// Enqueue a reduction of `image` owned by `principal`, returning the new
+    /// job id.
+    ///
+    /// Rejected with [`SubmitError::Draining`] once shutdown has begun.
+    pub async fn submit(&self, principal: &str, image: ImageId) -> Result<JobId, SubmitError> {
+        if self.draining.load(Ordering::SeqCst) {
+            return Err(SubmitError::Draining);
+        }
+        let id = JobId::new();
+        self.owners
+            .write()
+            .await
+            .insert(id, principal.to_string());
+        self.jobs.write().await.insert(
+            id,
+            JobState {
+                status: JobStatus::Queued,
+                progress: 0.0,
+                result_url: None,
+            },
+        );
+        // Send failure only happens once all workers are gone (shutdown); the
+        // job then simply stays queued, which the drain logic reports.
+        let _ = self.tx.send(Task { id, image });
+        Ok(id)
+    }
+
+    /// Current state of `id`, or `None` if it was never submitted.
+    pub async fn status(&self, id: JobId) -> Option<JobState> {
+        self.jobs.read().await.get(&id).cloned()
+    }
+
+    /// Whether `principal` submitted job `id`.
+    pub async fn is_owner(&self, id: JobId, principal: &str) -> bool {
+        self.owners
+            .read()
+            .await
+            .get(&id)
+            .is_some_and(|owner| owner == principal)
+    }
+
+    /// Whether the manager has begun draining and is refusing new jobs.
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+
+    /// Number of jobs still queued or running.
+    pub async fn in_flight(&self) -> usize {
+        self.jobs
+            .read()
+            .await
+            .values()
+            .filter(|s| matches!(s.status, JobStatus::Queued | JobStatus::Running))
+            .count()
+    }
+
+    /// Stop accepting new jobs and wait up to `grace` for the in-flight set to
+    /// drain. Running reductions observe the drain flag between sources and
+    /// checkpoint their partial catalog to disk before exiting, so a job
+    /// interrupted by shutdown still leaves the sources it had measured so far
+    /// available for download rather than nothing.
+    pub async fn drain(&self, grace: Duration) {
+        self.draining.store(true, Ordering::SeqCst);
+        let deadline = tokio::time::Instant::now() + grace;
+        while self.in_flight().await > 0 {
+            if tokio::time::Instant::now() >= deadline {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    }
+}
+
+/// How many sources to measure between partial-catalog checkpoints.
+const CHECKPOINT_EVERY: usize = 64;
+
+fn spawn_pool(
+    rx: mpsc::UnboundedReceiver<Task>,
+    jobs: Arc<RwLock<HashMap<JobId, JobState>>>,
+    storage: Storage,
+    config: Config,
+    draining: Arc<AtomicBool>,
+    workers: usize,
+) {
+    let rx = Arc::new(tokio::sync::Mutex::new(rx));
+    for _ in 0..workers.max(1) {
+        let rx = rx.clone();
+        let jobs = jobs.clone();
+        let storage = storage.clone();
+        let config = config.clone();
+        let draining = draining.clone();
+        tokio::spawn(async move {
+            loop {
+                let task = {
+                    let mut guard = rx.lock().await;
+                    guard.recv().await
+                };
+                let Some(task) = task else { break };
+                process(&task, &jobs, &storage, &config, &draining).await;
+            }
+        });
+    }
+}
+
+async fn process(
+    task: &Task,
+    jobs: &Arc<RwLock<HashMap<JobId, JobState>>>,
+    storage: &Storage,
+    config: &Config,
+    draining: &Arc<AtomicBool>,
+) {
+    set_status(jobs, task.id, JobStatus::Running, 0.0, None).await;
+
+    let result = reduce_to_disk(task, jobs, storage, config, draining).await;
+    match result {
+        Ok(url) => set_status(jobs, task.id, JobStatus::Done, 1.0, Some(url)).await,
+        Err(_) => set_status(jobs, task.id, JobStatus::Failed, 1.0, None).await,
+    }
+}
+
+async fn reduce_to_disk(
+    task: &Task,
+    jobs: &Arc<RwLock<HashMap<JobId, JobState>>>,
+    storage: &Storage,
+    config: &Config,
+    draining: &Arc<AtomicBool>,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let bytes = storage.load_frame(task.image).await?;
+    let image = fits::read_image(&bytes)?;
+
+    let sources = photometry::detect_sources(&image, 5.0);
+    let total = sources.len();
+    let mut catalog = Vec::with_capacity(total);
+
+    for (done, (x, y)) in sources.into_iter().enumerate() {
+        catalog.push(photometry::measure_source(
+            &image,
+            x,
+            y,
+            &config.apertures,
+            config.gain,
+            config.zeropoint,
+        ));
+
+        // Checkpoint the partial catalog periodically and whenever shutdown has
+        // begun, so an interrupted reduction still leaves its measured sources
+        // on disk.
+        let draining_now = draining.load(Ordering::SeqCst);
+        if draining_now || (done + 1) % CHECKPOINT_EVERY == 0 {
+            write_products(storage, task.image, &catalog).await?;
+            let progress = if total == 0 {
+                1.0
+            } else {
+                (done + 1) as f32 / total as f32
+            };
+            set_progress(jobs, task.id, progress).await;
+            if draining_now {
+                return Ok(format!("/results/{}", task.image));
+            }
+        }
+    }
+
+    write_products(storage, task.image, &catalog).await?;
+    Ok(format!("/results/{}", task.image))
+}
+
+async fn write_products(
+    storage: &Storage,
+    image: ImageId,
+    catalog: &[photometry::SourcePhotometry],
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    write_product(storage, image, "json", &catalog::to_json(catalog)?).await?;
+    write_product(storage, image, "csv", catalog::to_csv(catalog).as_bytes()).await?;
+    write_product(storage, image, "fits", &catalog::to_fits(catalog)).await?;
+    Ok(())
+}
+
+async fn write_product(
+    storage: &Storage,
+    image: ImageId,
+    ext: &str,
+    bytes: &[u8],
+) -> std::io::Result<()> {
+    let path = storage.result_path(image, ext);
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(&path, bytes).await
+}
+
+async fn set_status(
+    jobs: &Arc<RwLock<HashMap<JobId, JobState>>>,
+    id: JobId,
+    status: JobStatus,
+    progress: f32,
+    result_url: Option<String>,
+) {
+    if let Some(state) = jobs.write().await.get_mut(&id) {
+        state.status = status;
+        state.progress = progress;
+        state.result_url = result_url;
+    }
+}
+
+/// Update only the progress fraction of a running job.
+async fn set_progress(jobs: &Arc<RwLock<HashMap<JobId, JobState>>>, id: JobId, progress: f32) {
+    if let Some(state) = jobs.write().await.get_mut(&id) {
+        state.progress = progress;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager() -> JobManager {
+        let config = Config::default();
+        let storage = Storage::new(config.storage_dir.clone());
+        JobManager::new(storage, config, 2)
+    }
+
+    #[tokio::test]
+    async fn test_submit_returns_queued_job() {
+        let mgr = manager();
+        let id = mgr.submit("tester", ImageId::new()).await.expect("accepted");
+        let state = mgr.status(id).await.expect("job exists");
+        // Workers may have picked it up already; either way it is tracked.
+        assert!(matches!(
+            state.status,
+            JobStatus::Queued | JobStatus::Running | JobStatus::Failed
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_job_is_none() {
+        let mgr = manager();
+        assert!(mgr.status(JobId::new()).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_enqueue_and_poll() {
+        let mgr = manager();
+        let mut handles = Vec::new();
+        for _ in 0..16 {
+            let mgr = mgr.clone();
+            handles.push(tokio::spawn(async move {
+                mgr.submit("tester", ImageId::new()).await.expect("accepted")
+            }));
+        }
+        let mut ids = Vec::new();
+        for handle in handles {
+            ids.push(handle.await.expect("submit task panicked"));
+        }
+        for id in ids {
+            assert!(mgr.status(id).await.is_some());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_drain_rejects_new_submissions() {
+        let mgr = manager();
+        mgr.drain(Duration::from_millis(100)).await;
+        assert!(mgr.is_draining());
+        assert_eq!(
+            mgr.submit("tester", ImageId::new()).await,
+            Err(SubmitError::Draining)
+        );
+    }
+}
@@ -0,0 +1,8 @@
+pub mod catalog;
+pub mod config;
+pub mod fits;
+pub mod jobs;
+pub mod middleware;
+pub mod photometry;
+pub mod routes;
+pub mod storage;
@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+use photometoria_api::config::Config;
+use photometoria_api::routes::{create_router, AppState};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::default();
+    let grace = Duration::from_secs(config.shutdown_grace_secs);
+    let state = AppState::new(config);
+    let jobs = state.jobs.clone();
+
+    let app = create_router(state);
+    let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await?;
+
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal(jobs, grace))
+        .await?;
+
+    Ok(())
+}
+
+/// Resolve once an interrupt or termination signal arrives, then drain the job
+/// subsystem: running reductions stop at the next source and checkpoint their
+/// partial products to disk, waiting at most the grace window before the
+/// server exits.
+async fn shutdown_signal(jobs: photometoria_api::jobs::JobManager, grace: Duration) {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+
+    jobs.drain(grace).await;
+}
@@ -0,0 +1,52 @@
+//! API-key authentication for mutating routes.
+//!
+//! The layer checks a bearer token (or `X-API-Key` header) against the keys
+//! configured in [`Config`], rejecting missing or unknown keys with `401`. On
+//! success the authenticated [`Principal`] is inserted into the request
+//! extensions so downstream handlers can scope stored images and jobs per user.
+
+use axum::{
+    extract::{Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::config::Config;
+
+/// The principal a request authenticated as.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Principal(pub String);
+
+/// Middleware guarding the mutating endpoints.
+pub async fn require_api_key(
+    State(config): State<Config>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let key = extract_key(&request).ok_or(StatusCode::UNAUTHORIZED)?;
+    let principal = config.api_keys.get(&key).ok_or(StatusCode::UNAUTHORIZED)?;
+
+    request
+        .extensions_mut()
+        .insert(Principal(principal.clone()));
+
+    Ok(next.run(request).await)
+}
+
+/// Pull the presented key from the `Authorization: Bearer` or `X-API-Key`
+/// header, if present.
+fn extract_key(request: &Request) -> Option<String> {
+    let headers = request.headers();
+    if let Some(bearer) = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+    {
+        return Some(bearer.trim().to_string());
+    }
+    headers
+        .get("x-api-key")
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.trim().to_string())
+}
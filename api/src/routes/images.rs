@@ -0,0 +1,145 @@
+//! Frame upload and reduction route.
+
+use axum::{
+    extract::{Multipart, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::fits;
+use crate::middleware::auth::Principal;
+use crate::photometry::{self, SourcePhotometry};
+use crate::storage::Storage;
+
+/// Response returned once an uploaded frame has been reduced.
+#[derive(Debug, Serialize)]
+pub struct UploadResponse {
+    pub image_id: String,
+    pub catalog: Vec<SourcePhotometry>,
+}
+
+/// `POST /images` — accept a multipart FITS upload, persist it, and return the
+/// photometric catalog of detected sources.
+pub async fn upload(
+    State(storage): State<Storage>,
+    State(config): State<Config>,
+    Extension(principal): Extension<Principal>,
+    mut multipart: Multipart,
+) -> Result<Json<UploadResponse>, (StatusCode, String)> {
+    let mut frame: Option<Vec<u8>> = None;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?
+    {
+        let bytes = field
+            .bytes()
+            .await
+            .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+        frame = Some(bytes.to_vec());
+    }
+
+    let frame = frame.ok_or((StatusCode::BAD_REQUEST, "no file field in upload".to_string()))?;
+
+    let image = fits::read_image(&frame)
+        .map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, e.to_string()))?;
+
+    let image_id = storage
+        .store_frame(&principal.0, &frame)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let catalog = photometry::reduce(&image, &config);
+
+    Ok(Json(UploadResponse {
+        image_id: image_id.to_string(),
+        catalog,
+    }))
+}
+
+/// Request body for `POST /images/fetch`.
+#[derive(Debug, Deserialize)]
+pub struct FetchRequest {
+    pub url: String,
+}
+
+/// Response for a successful remote fetch.
+#[derive(Debug, Serialize)]
+pub struct FetchResponse {
+    pub image_id: String,
+}
+
+/// `POST /images/fetch` — download a FITS frame from a remote URL and store it.
+///
+/// The body is read chunk-by-chunk into a bounded in-memory buffer and aborted
+/// as soon as it exceeds the configured `max_size`, so a multi-gigabyte survey
+/// tile can never grow the buffer past that ceiling. The remote must declare a
+/// content type on the configured allowlist.
+pub async fn fetch(
+    State(storage): State<Storage>,
+    State(config): State<Config>,
+    Extension(principal): Extension<Principal>,
+    Json(req): Json<FetchRequest>,
+) -> Result<Json<FetchResponse>, (StatusCode, String)> {
+    let response = reqwest::get(&req.url)
+        .await
+        .map_err(|e| (StatusCode::BAD_GATEWAY, e.to_string()))?;
+
+    if !response.status().is_success() {
+        return Err((
+            StatusCode::BAD_GATEWAY,
+            format!("remote returned {}", response.status()),
+        ));
+    }
+
+    // A missing Content-Type is treated as disallowed rather than a free pass.
+    let content_type = response
+        .headers()
+        .get(axum::http::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .ok_or((
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "remote did not declare a content type".to_string(),
+        ))?;
+    let media = content_type.split(';').next().unwrap_or("").trim();
+    if !config
+        .fetch
+        .allowed_content_types
+        .iter()
+        .any(|allowed| allowed == media)
+    {
+        return Err((
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            format!("content type {media} not allowed"),
+        ));
+    }
+
+    let mut bytes = Vec::new();
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| (StatusCode::BAD_GATEWAY, e.to_string()))?;
+        if bytes.len() as u64 + chunk.len() as u64 > config.fetch.max_size {
+            return Err((
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!("remote frame exceeds {} bytes", config.fetch.max_size),
+            ));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    // Validate it parses as FITS before committing it to storage.
+    fits::read_image(&bytes)
+        .map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, e.to_string()))?;
+
+    let image_id = storage
+        .store_frame(&principal.0, &bytes)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(FetchResponse {
+        image_id: image_id.to_string(),
+    }))
+}
@@ -0,0 +1,67 @@
+//! Asynchronous reduction job routes.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::jobs::{JobManager, JobState, SubmitError};
+use crate::middleware::auth::Principal;
+use crate::storage::{ImageId, Storage};
+
+/// Request body for `POST /jobs`.
+#[derive(Debug, Deserialize)]
+pub struct SubmitRequest {
+    pub image_id: String,
+}
+
+/// Response acknowledging an enqueued job.
+#[derive(Debug, Serialize)]
+pub struct SubmitResponse {
+    pub job_id: String,
+}
+
+/// `POST /jobs` — enqueue a reduction and return its id immediately.
+///
+/// The caller may only reduce an image they own.
+pub async fn submit(
+    State(jobs): State<JobManager>,
+    State(storage): State<Storage>,
+    Extension(principal): Extension<Principal>,
+    Json(req): Json<SubmitRequest>,
+) -> Result<Json<SubmitResponse>, (StatusCode, String)> {
+    let image = ImageId::parse(&req.image_id)
+        .ok_or((StatusCode::BAD_REQUEST, "invalid image id".to_string()))?;
+    if !storage.is_owner(image, &principal.0).await {
+        return Err((StatusCode::NOT_FOUND, "unknown image".to_string()));
+    }
+    let job_id = jobs.submit(&principal.0, image).await.map_err(|e| match e {
+        SubmitError::Draining => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "server is shutting down".to_string(),
+        ),
+    })?;
+    Ok(Json(SubmitResponse {
+        job_id: job_id.to_string(),
+    }))
+}
+
+/// `GET /jobs/{id}` — poll the status of a previously submitted job. Only the
+/// principal that submitted the job may poll it.
+pub async fn status(
+    State(jobs): State<JobManager>,
+    Extension(principal): Extension<Principal>,
+    Path(id): Path<String>,
+) -> Result<Json<JobState>, (StatusCode, String)> {
+    let job_id = crate::jobs::JobId::parse(&id)
+        .ok_or((StatusCode::BAD_REQUEST, "invalid job id".to_string()))?;
+    if !jobs.is_owner(job_id, &principal.0).await {
+        return Err((StatusCode::NOT_FOUND, "unknown job".to_string()));
+    }
+    match jobs.status(job_id).await {
+        Some(state) => Ok(Json(state)),
+        None => Err((StatusCode::NOT_FOUND, "unknown job".to_string())),
+    }
+}
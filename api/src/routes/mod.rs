@@ -1,7 +1,78 @@
-use axum::{routing::get, Router};
+use axum::{
+    extract::FromRef,
+    middleware::from_fn_with_state,
+    routing::{get, post},
+    Router,
+};
 
-pub fn create_router() -> Router {
-    Router::new().route("/version", get(version))
+use crate::config::Config;
+use crate::jobs::JobManager;
+use crate::middleware::auth::require_api_key;
+use crate::storage::Storage;
+
+mod images;
+mod jobs;
+mod results;
+
+/// Shared state threaded into every handler.
+///
+/// Individual handlers extract only the piece they need via [`FromRef`], so the
+/// storage layer and config can grow independently.
+#[derive(Clone)]
+pub struct AppState {
+    pub config: Config,
+    pub storage: Storage,
+    pub jobs: JobManager,
+}
+
+impl AppState {
+    pub fn new(config: Config) -> Self {
+        let storage = Storage::new(config.storage_dir.clone());
+        let jobs = JobManager::new(storage.clone(), config.clone(), 4);
+        Self {
+            config,
+            storage,
+            jobs,
+        }
+    }
+}
+
+impl FromRef<AppState> for Config {
+    fn from_ref(state: &AppState) -> Self {
+        state.config.clone()
+    }
+}
+
+impl FromRef<AppState> for Storage {
+    fn from_ref(state: &AppState) -> Self {
+        state.storage.clone()
+    }
+}
+
+impl FromRef<AppState> for JobManager {
+    fn from_ref(state: &AppState) -> Self {
+        state.jobs.clone()
+    }
+}
+
+pub fn create_router(state: AppState) -> Router {
+    // Every per-user route sits behind the API-key layer so the authenticated
+    // principal is available for ownership scoping; only `/version` is public.
+    let protected = Router::new()
+        .route("/images", post(images::upload))
+        .route("/images/fetch", post(images::fetch))
+        .route("/jobs", post(jobs::submit))
+        .route("/jobs/{id}", get(jobs::status))
+        .route("/results/{id}", get(results::download))
+        .route_layer(from_fn_with_state(
+            state.config.clone(),
+            require_api_key,
+        ));
+
+    Router::new()
+        .route("/version", get(version))
+        .merge(protected)
+        .with_state(state)
 }
 
 async fn version() -> &'static str {
@@ -15,9 +86,21 @@ mod tests {
     use http_body_util::BodyExt;
     use tower::ServiceExt;
 
+    fn test_state() -> AppState {
+        AppState::new(Config::default())
+    }
+
+    fn state_with_key(key: &str) -> AppState {
+        let mut config = Config::default();
+        config
+            .api_keys
+            .insert(key.to_string(), "tester".to_string());
+        AppState::new(config)
+    }
+
     #[tokio::test]
     async fn test_version_returns_package_version() {
-        let app = create_router();
+        let app = create_router(test_state());
         let request = Request::get("/version").body(Body::empty()).unwrap();
         let response = app.oneshot(request).await.unwrap();
 
@@ -28,4 +111,49 @@ mod tests {
 
         assert_eq!(body_str, env!("CARGO_PKG_VERSION"));
     }
+
+    #[tokio::test]
+    async fn test_public_route_needs_no_key() {
+        let app = create_router(state_with_key("secret"));
+        let request = Request::get("/version").body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), 200);
+    }
+
+    #[tokio::test]
+    async fn test_write_route_rejects_missing_key() {
+        let app = create_router(state_with_key("secret"));
+        let request = Request::post("/jobs")
+            .header("content-type", "application/json")
+            .body(Body::from("{\"image_id\":\"x\"}"))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), 401);
+    }
+
+    #[tokio::test]
+    async fn test_write_route_rejects_invalid_key() {
+        let app = create_router(state_with_key("secret"));
+        let request = Request::post("/jobs")
+            .header("authorization", "Bearer wrong")
+            .header("content-type", "application/json")
+            .body(Body::from("{\"image_id\":\"x\"}"))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), 401);
+    }
+
+    #[tokio::test]
+    async fn test_write_route_accepts_valid_key() {
+        let app = create_router(state_with_key("secret"));
+        let request = Request::post("/jobs")
+            .header("authorization", "Bearer secret")
+            .header("content-type", "application/json")
+            .body(Body::from("{\"image_id\":\"not-a-uuid\"}"))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        // Authenticated, so it reaches the handler and fails on the bad id
+        // rather than being rejected by the auth layer.
+        assert_eq!(response.status(), 400);
+    }
 }
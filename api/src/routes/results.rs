@@ -0,0 +1,51 @@
+//! Download route for reduced photometry products.
+
+use axum::{
+    body::Body,
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::Response,
+    Extension,
+};
+use tokio_util::io::ReaderStream;
+
+use crate::catalog::Format;
+use crate::middleware::auth::Principal;
+use crate::storage::{ImageId, Storage};
+
+/// `GET /results/{id}` — stream a reduced product, negotiating CSV, JSON, or
+/// FITS from the `Accept` header. The body is streamed from disk rather than
+/// buffered so large catalogs stay cheap to serve. Only the principal that owns
+/// the source image may download its products.
+pub async fn download(
+    State(storage): State<Storage>,
+    Extension(principal): Extension<Principal>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, (StatusCode, String)> {
+    let image = ImageId::parse(&id)
+        .ok_or((StatusCode::BAD_REQUEST, "invalid result id".to_string()))?;
+    if !storage.is_owner(image, &principal.0).await {
+        return Err((StatusCode::NOT_FOUND, "result not found".to_string()));
+    }
+
+    let accept = headers
+        .get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok());
+    let format = Format::negotiate(accept);
+
+    let path = storage.result_path(image, format.ext());
+    let file = tokio::fs::File::open(&path)
+        .await
+        .map_err(|_| (StatusCode::NOT_FOUND, "result not found".to_string()))?;
+
+    let stream = ReaderStream::new(file);
+    let disposition = format!("attachment; filename=\"{id}.{}\"", format.ext());
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, format.mime())
+        .header(header::CONTENT_DISPOSITION, disposition)
+        .body(Body::from_stream(stream))
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
@@ -0,0 +1,103 @@
+//! On-disk storage for uploaded frames and reduced products.
+//!
+//! Frames are keyed by an opaque [`ImageId`] and laid out under the configured
+//! storage directory. The same layer is reused by the upload, fetch, and result
+//! routes so there is a single place that knows where bytes live on disk.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use tokio::io::AsyncWriteExt;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Opaque identifier for a stored image, stable across process restarts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ImageId(Uuid);
+
+impl ImageId {
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Uuid::parse_str(s).ok().map(Self)
+    }
+}
+
+impl Default for ImageId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for ImageId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Filesystem-backed blob store rooted at a single directory.
+///
+/// Alongside the on-disk blobs the store tracks which principal owns each
+/// image, so handlers can reject cross-user access to frames and results.
+#[derive(Debug, Clone)]
+pub struct Storage {
+    root: PathBuf,
+    owners: Arc<RwLock<HashMap<ImageId, String>>>,
+}
+
+impl Storage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            owners: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Persist the raw bytes of an uploaded frame owned by `principal`,
+    /// returning its assigned id.
+    pub async fn store_frame(&self, principal: &str, bytes: &[u8]) -> std::io::Result<ImageId> {
+        let id = ImageId::new();
+        self.write(&self.frame_path(id), bytes).await?;
+        self.owners.write().await.insert(id, principal.to_string());
+        Ok(id)
+    }
+
+    /// Whether `principal` owns `image`.
+    pub async fn is_owner(&self, image: ImageId, principal: &str) -> bool {
+        self.owners
+            .read()
+            .await
+            .get(&image)
+            .is_some_and(|owner| owner == principal)
+    }
+
+    /// Read back the raw bytes of a previously stored frame.
+    pub async fn load_frame(&self, id: ImageId) -> std::io::Result<Vec<u8>> {
+        tokio::fs::read(self.frame_path(id)).await
+    }
+
+    /// Path of the frame blob for `id`, under the storage root.
+    pub fn frame_path(&self, id: ImageId) -> PathBuf {
+        self.root.join("frames").join(id.to_string())
+    }
+
+    /// Path of a reduced product for `id` with the given extension, under the
+    /// storage root.
+    pub fn result_path(&self, id: ImageId, ext: &str) -> PathBuf {
+        self.root.join("results").join(format!("{id}.{ext}"))
+    }
+
+    async fn write(&self, path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let mut file = tokio::fs::File::create(path).await?;
+        file.write_all(bytes).await?;
+        file.flush().await?;
+        Ok(())
+    }
+}